@@ -4,8 +4,216 @@
     // 111122 meets the criteria (even though 1 is repeated more than twice, it still contains a double 22).
 
 use digits_iterator::*;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::u8::MAX as U8_MAX;
 
+/// How often (in candidates scanned) the resumable search writes a checkpoint.
+const CHECKPOINT_INTERVAL: u32 = 10_000;
+
+/// A snapshot of an in-progress `start..=end` scan, serialized to a
+/// sidecar file so a long-running search can be killed and picked back
+/// up without recomputing everything it's already seen.
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    last_candidate: u32,
+    matches_so_far: usize,
+    range_start: u32,
+    range_end: u32,
+}
+
+impl Checkpoint {
+    /// Sidecars live under the system temp dir rather than
+    /// `src/inputs/`: they're scratch state for a single in-progress
+    /// scan, not puzzle input worth checking in.
+    fn path_for(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("aoc-day4-{}.resume.json", name))
+    }
+
+    /// Loads the checkpoint for `name`, discarding it if it was taken
+    /// for a different range than the one being requested now, or if
+    /// `last_candidate` is already at or past `range_end` (such a
+    /// checkpoint leaves nothing to resume, so the scan starts fresh).
+    fn load(name: &str, range_start: u32, range_end: u32) -> Option<Self> {
+        let contents = fs::read_to_string(Self::path_for(name)).ok()?;
+        let checkpoint: Checkpoint = serde_json::from_str(&contents).ok()?;
+        if checkpoint.range_start == range_start
+            && checkpoint.range_end == range_end
+            && checkpoint.last_candidate < range_end
+        {
+            Some(checkpoint)
+        } else {
+            None
+        }
+    }
+
+    fn save(&self, name: &str) -> io::Result<()> {
+        let contents = serde_json::to_string(self).expect("Checkpoint always serializes");
+        fs::write(Self::path_for(name), contents)
+    }
+
+    fn clear(name: &str) -> io::Result<()> {
+        match fs::remove_file(Self::path_for(name)) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Serial password scan that checkpoints its progress to a temp-dir
+/// sidecar (see [`Checkpoint::path_for`]) every [`CHECKPOINT_INTERVAL`]
+/// candidates, resuming from the last checkpoint (if any) that matches
+/// the requested range, and deleting it once the scan finishes cleanly.
+fn count_valid_passwords_resumable(name: &str, start: u32, end: u32) -> usize {
+    PasswordSearch::new(name, start, end).run()
+}
+
+/// A point-in-time snapshot of a running [`PasswordSearch`], shared
+/// with its optional progress server via `Arc<Mutex<_>>`.
+#[derive(Debug, Default, Clone, Copy)]
+struct ProgressState {
+    current: u32,
+    matches: usize,
+    range_start: u32,
+    range_end: u32,
+}
+
+impl ProgressState {
+    fn percent(&self) -> f64 {
+        if self.range_end <= self.range_start {
+            return 100.0;
+        }
+        let scanned = self.current.saturating_sub(self.range_start);
+        let total = self.range_end - self.range_start;
+        f64::from(scanned) / f64::from(total) * 100.0
+    }
+
+    fn to_json(self) -> String {
+        format!(
+            r#"{{"current":{},"matches":{},"range_start":{},"range_end":{},"percent":{}}}"#,
+            self.current,
+            self.matches,
+            self.range_start,
+            self.range_end,
+            self.percent()
+        )
+    }
+}
+
+fn spawn_progress_server(addr: &str, state: Arc<Mutex<ProgressState>>) {
+    let server = tiny_http::Server::http(addr).expect("failed to bind progress server");
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let body = state.lock().unwrap().to_json();
+            let header =
+                tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                    .unwrap();
+            let response = tiny_http::Response::from_string(body).with_header(header);
+            let _ = request.respond(response);
+        }
+    });
+}
+
+/// Builder for a checkpointed password search that can optionally serve
+/// its live progress as JSON over HTTP, so a multi-minute scan is
+/// observable remotely without adding stdout spam.
+struct PasswordSearch {
+    name: String,
+    start: u32,
+    end: u32,
+    progress_server_addr: Option<String>,
+}
+
+impl PasswordSearch {
+    fn new(name: &str, start: u32, end: u32) -> Self {
+        PasswordSearch {
+            name: name.to_string(),
+            start,
+            end,
+            progress_server_addr: None,
+        }
+    }
+
+    /// Opts into a background HTTP server bound to `addr` that answers
+    /// GET `/` with the search's current progress as JSON. Searches
+    /// that don't call this stay completely silent, as before.
+    fn with_progress_server(mut self, addr: &str) -> Self {
+        self.progress_server_addr = Some(addr.to_string());
+        self
+    }
+
+    fn run(self) -> usize {
+        let (resume_from, matches_so_far) = match Checkpoint::load(&self.name, self.start, self.end)
+        {
+            Some(checkpoint) => (checkpoint.last_candidate + 1, checkpoint.matches_so_far),
+            None => (self.start, 0),
+        };
+
+        let progress = Arc::new(Mutex::new(ProgressState {
+            current: resume_from,
+            matches: matches_so_far,
+            range_start: self.start,
+            range_end: self.end,
+        }));
+
+        if let Some(addr) = &self.progress_server_addr {
+            spawn_progress_server(addr, Arc::clone(&progress));
+        }
+
+        let mut matches = matches_so_far;
+        for candidate in resume_from..=self.end {
+            if is_valid_password(candidate, self.start, self.end) {
+                matches += 1;
+            }
+            if (candidate - self.start) % CHECKPOINT_INTERVAL == 0 {
+                Checkpoint {
+                    last_candidate: candidate,
+                    matches_so_far: matches,
+                    range_start: self.start,
+                    range_end: self.end,
+                }
+                .save(&self.name)
+                .expect("failed to write checkpoint");
+                let mut state = progress.lock().unwrap();
+                state.current = candidate;
+                state.matches = matches;
+            }
+        }
+
+        Checkpoint::clear(&self.name).expect("failed to clear checkpoint");
+        matches
+    }
+}
+
+/// Counts valid passwords in `start..=end` by splitting the range into
+/// `num_cpus::get() * 32` contiguous chunks and scanning each chunk on
+/// a rayon thread, summing the per-chunk counts.
+fn count_valid_passwords_parallel(start: u32, end: u32) -> usize {
+    let chunk_count = (num_cpus::get() * 32) as u32;
+    let total = end - start + 1;
+    let chunk_size = (total + chunk_count - 1) / chunk_count;
+
+    (0..chunk_count)
+        .into_par_iter()
+        .map(|i| {
+            let chunk_start = start + i * chunk_size;
+            if chunk_start > end {
+                return 0;
+            }
+            let chunk_end = (chunk_start + chunk_size - 1).min(end);
+            (chunk_start..=chunk_end)
+                .filter(|candidate| is_valid_password(*candidate, start, end))
+                .count()
+        })
+        .sum()
+}
+
 fn is_valid_password(candidate: u32, range_start: u32, range_end: u32) -> bool {
     if candidate.digits().count() != 6 {
         return false;
@@ -101,4 +309,140 @@ mod tests {
     fn smoke_simple_program_3() {
         assert!(is_valid_password(111122, 111122, 111122));
     }
+
+    #[test]
+    fn test_parallel_count_matches_serial_count() {
+        let (start, end) = parse("152085-670283");
+        let serial_count = (start..end)
+            .filter(|num| is_valid_password(*num, start, end))
+            .count();
+        let parallel_count = count_valid_passwords_parallel(start, end - 1);
+        assert_eq!(parallel_count, serial_count);
+    }
+
+    #[test]
+    fn test_resumable_count_matches_serial_count_and_cleans_up() {
+        let (start, end) = parse("152085-670283");
+        let serial_count = (start..end)
+            .filter(|num| is_valid_password(*num, start, end))
+            .count();
+        let resumable_count =
+            count_valid_passwords_resumable("day4-test-resumable", start, end - 1);
+        assert_eq!(resumable_count, serial_count);
+        assert!(!Checkpoint::path_for("day4-test-resumable").exists());
+    }
+
+    #[test]
+    fn test_resumable_count_discards_checkpoint_for_different_range() {
+        Checkpoint {
+            last_candidate: 150000,
+            matches_so_far: 999,
+            range_start: 100000,
+            range_end: 200000,
+        }
+        .save("day4-test-resume-point")
+        .expect("failed to save checkpoint");
+        // The saved checkpoint is for a different range, so it should
+        // be discarded rather than resumed from.
+        let count = count_valid_passwords_resumable("day4-test-resume-point", 111111, 111200);
+        assert_eq!(
+            count,
+            (111111..=111200)
+                .filter(|num| is_valid_password(*num, 111111, 111200))
+                .count()
+        );
+        assert!(!Checkpoint::path_for("day4-test-resume-point").exists());
+    }
+
+    #[test]
+    fn test_resumable_count_resumes_from_valid_checkpoint() {
+        let (start, end) = (111111, 111200);
+        let resume_point = 111150;
+        let matches_before_resume_point = (start..=resume_point)
+            .filter(|num| is_valid_password(*num, start, end))
+            .count();
+        Checkpoint {
+            last_candidate: resume_point,
+            matches_so_far: matches_before_resume_point,
+            range_start: start,
+            range_end: end,
+        }
+        .save("day4-test-resume-valid")
+        .expect("failed to save checkpoint");
+
+        let serial_count = (start..=end)
+            .filter(|num| is_valid_password(*num, start, end))
+            .count();
+        let resumed_count = count_valid_passwords_resumable("day4-test-resume-valid", start, end);
+        assert_eq!(resumed_count, serial_count);
+        assert!(!Checkpoint::path_for("day4-test-resume-valid").exists());
+    }
+
+    #[test]
+    fn test_password_search_without_progress_server_matches_serial_count() {
+        let (start, end) = parse("152085-670283");
+        let serial_count = (start..end)
+            .filter(|num| is_valid_password(*num, start, end))
+            .count();
+        let search_count = PasswordSearch::new("day4-test-search", start, end - 1).run();
+        assert_eq!(search_count, serial_count);
+    }
+
+    #[test]
+    fn test_progress_state_percent() {
+        let state = ProgressState {
+            current: 150,
+            matches: 3,
+            range_start: 100,
+            range_end: 200,
+        };
+        assert_eq!(state.percent(), 50.0);
+    }
+
+    #[test]
+    fn test_progress_server_serves_json_progress() {
+        use std::io::{Read, Write};
+        use std::net::{TcpListener, TcpStream};
+        use std::time::Duration;
+
+        // Grabbing an OS-assigned free port this way (bind then drop)
+        // leaves a brief window before the progress server binds the
+        // same address, but it's the standard trick for a test-only
+        // free port without hardcoding one.
+        let addr = {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("failed to reserve a port");
+            listener.local_addr().unwrap().to_string()
+        };
+
+        let search = PasswordSearch::new("day4-test-progress-server", 111111, 111200)
+            .with_progress_server(&addr);
+        let handle = thread::spawn(move || search.run());
+
+        let mut response = String::new();
+        for _ in 0..50 {
+            if let Ok(mut stream) = TcpStream::connect(&addr) {
+                stream
+                    .write_all(b"GET / HTTP/1.0\r\n\r\n")
+                    .expect("failed to send request");
+                stream
+                    .read_to_string(&mut response)
+                    .expect("failed to read response");
+                if !response.is_empty() {
+                    break;
+                }
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        handle.join().expect("progress server search thread panicked");
+
+        let body = response
+            .split("\r\n\r\n")
+            .last()
+            .expect("response had no body");
+        assert!(body.contains("\"range_start\":111111"));
+        assert!(body.contains("\"range_end\":111200"));
+        assert!(body.contains("\"current\""));
+        assert!(body.contains("\"matches\""));
+    }
 }
\ No newline at end of file
@@ -1,25 +1,13 @@
 use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use std::fmt;
 
-trait InsertingIntoExistingValue<K, V> {
-    fn insert_into_existing_value(&mut self, key: K, value: V);
-}
+use crate::graph::Graph;
 
 type AdjacencyList<S> = HashMap<S, Vec<S>>;
 
-impl<S> InsertingIntoExistingValue<S, S> for AdjacencyList<S>
-where
-    S: Hash + Eq,
-{
-    fn insert_into_existing_value(&mut self, key: S, value: S) {
-        if let Some(mut existing) = self.remove(&key) {
-            existing.push(value);
-            self.insert(key, existing);
-        } else {
-            self.insert(key, vec![value]);
-        }
-    }
-}
+/// Sentinel parent id recorded for `COM`, the one object this puzzle
+/// never sees orbiting anything else.
+const NO_PARENT: u32 = u32::MAX;
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
 enum OrbitalObject {
@@ -38,47 +26,252 @@ impl From<&str> for OrbitalObject {
     }
 }
 
+impl OrbitalObject {
+    fn mermaid_label(&self) -> &str {
+        match self {
+            OrbitalObject::You => "YOU",
+            OrbitalObject::Santa => "SAN",
+            OrbitalObject::Object(name) => name,
+        }
+    }
+
+    /// A Mermaid-safe node id for this object: labels can contain
+    /// characters that would otherwise break Mermaid's grammar, so the
+    /// id replaces every character that isn't an ASCII alphanumeric
+    /// with an underscore.
+    fn mermaid_id(&self) -> String {
+        self.mermaid_label()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+}
+
+/// Maps each distinct [`OrbitalObject`] to a dense `u32` id, so the
+/// parse and traversal hot paths index flat `Vec`s instead of hashing
+/// a `String` (or the `Object(String)` variant it's wrapped in) on
+/// every step. The id for a given name is stable for the lifetime of
+/// the interner, so repeated queries can cache it.
+#[derive(Debug, Default)]
+struct Interner {
+    ids: HashMap<OrbitalObject, u32>,
+    objects: Vec<OrbitalObject>,
+}
+
+impl Interner {
+    /// Returns `object`'s id, assigning it the next free one if this is
+    /// the first time it's been seen.
+    fn intern(&mut self, object: OrbitalObject) -> u32 {
+        if let Some(&id) = self.ids.get(&object) {
+            return id;
+        }
+        let id = self.objects.len() as u32;
+        self.objects.push(object.clone());
+        self.ids.insert(object, id);
+        id
+    }
+
+    fn id_of(&self, object: &OrbitalObject) -> Option<u32> {
+        self.ids.get(object).copied()
+    }
+
+    fn object(&self, id: u32) -> &OrbitalObject {
+        &self.objects[id as usize]
+    }
+
+    fn len(&self) -> usize {
+        self.objects.len()
+    }
+}
+
+/// Walks every id's chain up through `parents` to `COM` exactly once,
+/// memoizing depths as it goes: once an id's depth is known (either
+/// because it's `COM` or because an earlier walk already reached it),
+/// every id still on the current walk's stack is assigned its depth in
+/// one unwind instead of being re-walked.
+fn compute_depths(parents: &[u32]) -> Vec<usize> {
+    const UNKNOWN: usize = usize::MAX;
+    let mut depths = vec![UNKNOWN; parents.len()];
+    for start in 0..parents.len() {
+        if depths[start] != UNKNOWN {
+            continue;
+        }
+        let mut stack = Vec::new();
+        let mut current = start;
+        loop {
+            if depths[current] != UNKNOWN {
+                let mut depth = depths[current];
+                while let Some(id) = stack.pop() {
+                    depth += 1;
+                    depths[id] = depth;
+                }
+                break;
+            }
+            let parent = parents[current];
+            if parent == NO_PARENT {
+                depths[current] = 0;
+                let mut depth = 0;
+                while let Some(id) = stack.pop() {
+                    depth += 1;
+                    depths[id] = depth;
+                }
+                break;
+            }
+            stack.push(current);
+            current = parent as usize;
+        }
+    }
+    depths
+}
+
+/// Walks a chain of ancestor ids, one per `next` call. Guards against
+/// cycles (only possible with malformed input) by tracking visited ids
+/// and stopping instead of looping forever.
+struct Ancestors<'a> {
+    orbit_map: &'a OrbitMap,
+    current: Option<u32>,
+    visited: HashSet<u32>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = OrbitalObject;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.take()?;
+        let parent = *self.orbit_map.parents.get(current as usize)?;
+        if parent == NO_PARENT || !self.visited.insert(parent) {
+            return None;
+        }
+        self.current = Some(parent);
+        Some(self.orbit_map.interner.object(parent).clone())
+    }
+}
+
 #[derive(Debug)]
 struct OrbitMap {
-    all_objects: HashSet<OrbitalObject>,
-    lookup: AdjacencyList<OrbitalObject>,
-    reverse_lookup: HashMap<OrbitalObject, OrbitalObject>,
+    interner: Interner,
+    /// `parents[id]` is the id of the object `id` directly orbits, or
+    /// [`NO_PARENT`] for `COM`.
+    parents: Vec<u32>,
+    /// `depths[id]` is `id`'s distance from `COM`.
+    depths: Vec<usize>,
+    /// Forward/reverse adjacency over interned ids, used for the
+    /// generic descendant traversal in `orbited_by` (see
+    /// [`crate::graph::Graph`]) rather than the perf-critical
+    /// `parents`/`depths` arrays above, which stay flat `Vec`s so the
+    /// hot paths (LCA, transfer counts) never hash an id.
+    children: Graph<u32>,
 }
 
 impl OrbitMap {
     fn parse(input: &str) -> Self {
-        let mut all_objects = HashSet::new();
-        let mut lookup = HashMap::new();
-        let mut reverse_lookup = HashMap::new();
+        let mut interner = Interner::default();
+        let mut parents = Vec::new();
+        let mut children = Graph::new();
         for line in input.split("\n") {
             let adjacency: Vec<&str> = line.split(")").take(2).map(str::trim).collect();
             if adjacency.len() != 2 {
                 continue;
             }
-            let (key, value) = (
-                OrbitalObject::from(adjacency[0]),
-                OrbitalObject::from(adjacency[1]),
-            );
-            let (reverse_key, reverse_value) = (value.clone(), key.clone());
-            all_objects.insert(key.clone());
-            all_objects.insert(reverse_key.clone());
-            lookup.insert_into_existing_value(key, value);
-            reverse_lookup.insert(reverse_key, reverse_value);
+            let parent_id = interner.intern(OrbitalObject::from(adjacency[0]));
+            let child_id = interner.intern(OrbitalObject::from(adjacency[1]));
+            if parents.len() <= child_id as usize {
+                parents.resize(child_id as usize + 1, NO_PARENT);
+            }
+            parents[child_id as usize] = parent_id;
+            children.add_edge(parent_id, child_id);
         }
+        parents.resize(interner.len(), NO_PARENT);
+        let depths = compute_depths(&parents);
         OrbitMap {
-            all_objects,
-            lookup,
-            reverse_lookup,
+            interner,
+            parents,
+            depths,
+            children,
+        }
+    }
+
+    /// The parent an object directly orbits, if it orbits anything.
+    fn parent_of(&self, obj: &OrbitalObject) -> Option<&OrbitalObject> {
+        let id = self.interner.id_of(obj)?;
+        let parent = self.parents[id as usize];
+        if parent == NO_PARENT {
+            None
+        } else {
+            Some(self.interner.object(parent))
         }
     }
 
+    /// Finds the lowest common ancestor of `a` and `b` by lifting
+    /// whichever is deeper up to the other's depth, then stepping both
+    /// up in lockstep until they coincide. O(depth), no allocation.
+    /// Returns `None` if `a` and `b` sit in disjoint trees (only
+    /// possible with malformed input: the puzzle always has one root)
+    /// and so share no common ancestor.
+    fn lowest_common_ancestor(&self, a: &OrbitalObject, b: &OrbitalObject) -> Option<OrbitalObject> {
+        let mut a = self.interner.id_of(a).unwrap();
+        let mut b = self.interner.id_of(b).unwrap();
+        let mut depth_a = self.depths[a as usize];
+        let mut depth_b = self.depths[b as usize];
+
+        while depth_a > depth_b {
+            a = self.parents[a as usize];
+            depth_a -= 1;
+        }
+        while depth_b > depth_a {
+            b = self.parents[b as usize];
+            depth_b -= 1;
+        }
+        loop {
+            if a == b {
+                return if a == NO_PARENT {
+                    None
+                } else {
+                    Some(self.interner.object(a).clone())
+                };
+            }
+            if a == NO_PARENT || b == NO_PARENT {
+                return None;
+            }
+            a = self.parents[a as usize];
+            b = self.parents[b as usize];
+        }
+    }
+
+    /// The number of orbital transfers needed to move from whatever
+    /// `a` orbits to whatever `b` orbits, via their lowest common
+    /// ancestor. `a`/`b` themselves aren't counted as hops, matching
+    /// the puzzle's `YOU`→`SAN` semantics. Returns `None` if either
+    /// object has no parent to transfer from (i.e. it's `COM` itself),
+    /// or if they share no common ancestor at all.
+    pub fn transfers_between(&self, a: &OrbitalObject, b: &OrbitalObject) -> Option<usize> {
+        let a_parent = self.parent_of(a)?;
+        let b_parent = self.parent_of(b)?;
+        let ancestor = self.lowest_common_ancestor(a_parent, b_parent)?;
+        let depth_a = self.depths[self.interner.id_of(a_parent).unwrap() as usize];
+        let depth_b = self.depths[self.interner.id_of(b_parent).unwrap() as usize];
+        let depth_ancestor = self.depths[self.interner.id_of(&ancestor).unwrap() as usize];
+        Some(depth_a + depth_b - 2 * depth_ancestor)
+    }
+
+    /// Counts the orbital transfers needed to move from whatever `a`
+    /// orbits to whatever `b` orbits, resolving both by name through
+    /// the interner. Returns `None` if either name is unknown, or if
+    /// either object has no parent to transfer from (i.e. it's `COM`
+    /// itself).
+    pub fn orbital_transfers_between(&self, a: &str, b: &str) -> Option<usize> {
+        let a = self.resolve(a).ok()?;
+        let b = self.resolve(b).ok()?;
+        self.transfers_between(&a, &b)
+    }
+
     fn object_has_direct_orbit_to_other_object(
         &self,
         obj1: &OrbitalObject,
         obj2: &OrbitalObject,
     ) -> bool {
-        if let Some(adjacency) = self.reverse_lookup.get(obj1) {
-            return adjacency == obj2;
+        if let Some(parent) = self.parent_of(obj1) {
+            return parent == obj2;
         }
         false
     }
@@ -88,57 +281,345 @@ impl OrbitMap {
         obj1: &OrbitalObject,
         obj2: &OrbitalObject,
     ) -> bool {
-        false
+        self.ancestors(obj1)
+            .enumerate()
+            .any(|(index, ancestor)| index >= 1 && &ancestor == obj2)
+    }
+
+    /// Iterates `obj`'s parent, grandparent, and so on up toward `COM`.
+    /// Malformed input could in principle make the orbit graph cyclic
+    /// rather than a clean tree, so the walk tracks visited ids and
+    /// stops rather than looping forever if it revisits one.
+    fn ancestors(&self, obj: &OrbitalObject) -> impl Iterator<Item = OrbitalObject> + '_ {
+        Ancestors {
+            orbit_map: self,
+            current: self.interner.id_of(obj),
+            visited: HashSet::new(),
+        }
+    }
+
+    fn is_ancestor_of(&self, ancestor: &OrbitalObject, obj: &OrbitalObject) -> bool {
+        self.ancestors(obj).any(|candidate| &candidate == ancestor)
+    }
+
+    fn is_descendant_of(&self, obj: &OrbitalObject, ancestor: &OrbitalObject) -> bool {
+        self.is_ancestor_of(ancestor, obj)
+    }
+
+    /// The ids of every object that directly orbits `id`.
+    fn children_of(&self, id: u32) -> impl Iterator<Item = u32> + '_ {
+        self.children.neighbors(&id).iter().copied()
     }
 
     fn construct_orbital_path_map(&self) -> AdjacencyList<OrbitalObject> {
         let mut path_map: AdjacencyList<OrbitalObject> = Default::default();
-        for obj in &self.all_objects {
+        for obj in &self.interner.objects {
             let mut current = obj;
             path_map.insert(obj.clone(), Default::default());
-            while self.reverse_lookup.contains_key(current) {
-                current = self.reverse_lookup.get(current).unwrap();
-                path_map.insert_into_existing_value(obj.clone(), current.clone());
+            while let Some(parent) = self.parent_of(current) {
+                current = parent;
+                path_map
+                    .entry(obj.clone())
+                    .or_insert_with(Vec::new)
+                    .push(current.clone());
             }
         }
         path_map
     }
 
     fn number_of_orbits(&self) -> usize {
-        self.construct_orbital_path_map()
-            .iter()
-            .map(|(_, v)| v.len())
-            .sum()
+        self.depths.iter().sum()
     }
 
-    fn number_of_orbital_transfers_from_you_to_santa(&self) -> usize {
+    /// The chain of objects you'd hop through to transfer from `a`'s
+    /// orbit to `b`'s, endpoints included. Returns `None` if either
+    /// object has no parent to transfer from (i.e. it's `COM` itself).
+    fn transfer_path_between(
+        &self,
+        a: &OrbitalObject,
+        b: &OrbitalObject,
+    ) -> Option<Vec<OrbitalObject>> {
         let path_map = self.construct_orbital_path_map();
-        assert!(path_map.contains_key(&OrbitalObject::You));
-        assert!(path_map.contains_key(&OrbitalObject::Santa));
-        let you_orbits = path_map.get(&OrbitalObject::You).unwrap();
-        let santa_orbits = path_map.get(&OrbitalObject::Santa).unwrap();
-        let mut last_matching_you_index = you_orbits.len() - 1;
-        let mut last_matching_santa_index = santa_orbits.len() - 1;
-        for (reverse_index, (you, santa)) in you_orbits
-            .iter()
-            .rev()
-            .zip(santa_orbits.iter().rev())
-            .enumerate()
+        let a_orbits = path_map.get(a)?;
+        let b_orbits = path_map.get(b)?;
+        if a_orbits.is_empty() || b_orbits.is_empty() {
+            return None;
+        }
+        let mut last_matching_a_index = a_orbits.len() - 1;
+        let mut last_matching_b_index = b_orbits.len() - 1;
+        for (reverse_index, (a_ancestor, b_ancestor)) in
+            a_orbits.iter().rev().zip(b_orbits.iter().rev()).enumerate()
         {
-            if you != santa {
+            if a_ancestor != b_ancestor {
                 break;
             }
-            last_matching_you_index = you_orbits.len() - reverse_index - 1;
-            last_matching_santa_index = santa_orbits.len() - reverse_index - 1;
+            last_matching_a_index = a_orbits.len() - reverse_index - 1;
+            last_matching_b_index = b_orbits.len() - reverse_index - 1;
+        }
+        Some(
+            a_orbits[..last_matching_a_index]
+                .iter()
+                .cloned()
+                .chain(b_orbits[..=last_matching_b_index].iter().rev().cloned())
+                .collect(),
+        )
+    }
+
+    /// The chain of objects you'd hop through to transfer from `YOU`'s
+    /// orbit to `SAN`'s, endpoints included.
+    fn transfer_path_you_to_santa(&self) -> Vec<OrbitalObject> {
+        self.transfer_path_between(&OrbitalObject::You, &OrbitalObject::Santa)
+            .expect("YOU and SAN always orbit something in a well-formed puzzle input")
+    }
+
+    fn number_of_orbital_transfers_from_you_to_santa(&self) -> usize {
+        self.transfers_between(&OrbitalObject::You, &OrbitalObject::Santa)
+            .expect("YOU and SAN always orbit something in a well-formed puzzle input")
+    }
+
+    /// Renders the parsed orbit graph as a Mermaid `graph TD` flowchart,
+    /// one `A --> B` edge per graph adjacency, with `YOU`/`SAN` styled
+    /// distinctly. When `highlight_transfer_path` is set, the objects
+    /// on the `YOU`→`SAN` transfer path are given a highlight style so
+    /// the route is visible at a glance.
+    fn to_mermaid(&self, highlight_transfer_path: bool) -> String {
+        let mut lines = vec!["graph TD".to_string()];
+
+        for (child_id, &parent_id) in self.parents.iter().enumerate() {
+            if parent_id == NO_PARENT {
+                continue;
+            }
+            let parent = self.interner.object(parent_id);
+            let child = self.interner.object(child_id as u32);
+            lines.push(format!(
+                "    {}[\"{}\"] --> {}[\"{}\"]",
+                parent.mermaid_id(),
+                parent.mermaid_label(),
+                child.mermaid_id(),
+                child.mermaid_label()
+            ));
+        }
+
+        lines.push(format!(
+            "    style {} fill:#f9f,stroke:#333,stroke-width:2px",
+            OrbitalObject::You.mermaid_id()
+        ));
+        lines.push(format!(
+            "    style {} fill:#9f9,stroke:#333,stroke-width:2px",
+            OrbitalObject::Santa.mermaid_id()
+        ));
+
+        if highlight_transfer_path {
+            for object in self.transfer_path_you_to_santa() {
+                lines.push(format!(
+                    "    style {} stroke:#f00,stroke-width:3px",
+                    object.mermaid_id()
+                ));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// Resolves a bare object name (`"COM"`, `"YOU"`, ...) against this
+    /// map, erroring out if nothing by that name was ever parsed.
+    fn resolve(&self, name: &str) -> Result<OrbitalObject, QueryError> {
+        let object = OrbitalObject::from(name);
+        if self.interner.id_of(&object).is_some() {
+            Ok(object)
+        } else {
+            Err(QueryError::UnknownObject(name.to_string()))
+        }
+    }
+
+    /// Evaluates a single query against this orbit map. Supported
+    /// operators: `orbits(X)`, `orbited_by(X)`, `common_ancestor(X, Y)`,
+    /// `path(X, Y)`, and `count_orbits`.
+    pub fn query(&self, input: &str) -> Result<QueryResult, QueryError> {
+        let tokens = tokenize(input);
+        let mut tokens = tokens.iter();
+        let operator = tokens
+            .next()
+            .ok_or_else(|| QueryError::Malformed("empty query".to_string()))?
+            .clone();
+
+        let args: Vec<&str> = match tokens.next() {
+            Some(open) if open == "(" => {
+                let mut args = Vec::new();
+                loop {
+                    match tokens.next() {
+                        Some(token) if token == ")" => break,
+                        Some(token) if token == "," => continue,
+                        Some(token) => args.push(token.as_str()),
+                        None => {
+                            return Err(QueryError::Malformed(format!(
+                                "unterminated argument list in {:?}",
+                                input
+                            )))
+                        }
+                    }
+                }
+                args
+            }
+            Some(token) => {
+                return Err(QueryError::Malformed(format!(
+                    "expected '(' after {:?}, found {:?}",
+                    operator, token
+                )))
+            }
+            None => Vec::new(),
+        };
+
+        match operator.as_str() {
+            "orbits" => {
+                let object = self.expect_one_arg(&operator, &args)?;
+                Ok(QueryResult::Objects(self.ancestors(&object).collect()))
+            }
+            "orbited_by" => {
+                let object = self.expect_one_arg(&operator, &args)?;
+                let start = self.interner.id_of(&object).unwrap();
+                let descendants = self
+                    .children
+                    .bfs(&start)
+                    .into_iter()
+                    .skip(1) // bfs includes `start` itself; `orbited_by` wants only descendants.
+                    .map(|id| self.interner.object(id).clone())
+                    .collect();
+                Ok(QueryResult::Objects(descendants))
+            }
+            "common_ancestor" => {
+                let (a, b) = self.expect_two_args(&operator, &args)?;
+                self.lowest_common_ancestor(&a, &b)
+                    .map(|ancestor| QueryResult::Objects(vec![ancestor]))
+                    .ok_or_else(|| {
+                        QueryError::NoCommonAncestor(args[0].to_string(), args[1].to_string())
+                    })
+            }
+            "path" => {
+                let (a, b) = self.expect_two_args(&operator, &args)?;
+                self.transfer_path_between(&a, &b)
+                    .map(QueryResult::Objects)
+                    .ok_or_else(|| QueryError::NoPath(args[0].to_string(), args[1].to_string()))
+            }
+            "count_orbits" => {
+                if !args.is_empty() {
+                    return Err(QueryError::ArityMismatch {
+                        operator,
+                        expected: 0,
+                        found: args.len(),
+                    });
+                }
+                Ok(QueryResult::Count(self.number_of_orbits()))
+            }
+            other => Err(QueryError::UnknownOperator(other.to_string())),
+        }
+    }
+
+    fn expect_one_arg(&self, operator: &str, args: &[&str]) -> Result<OrbitalObject, QueryError> {
+        if args.len() != 1 {
+            return Err(QueryError::ArityMismatch {
+                operator: operator.to_string(),
+                expected: 1,
+                found: args.len(),
+            });
+        }
+        self.resolve(args[0])
+    }
+
+    fn expect_two_args(
+        &self,
+        operator: &str,
+        args: &[&str],
+    ) -> Result<(OrbitalObject, OrbitalObject), QueryError> {
+        if args.len() != 2 {
+            return Err(QueryError::ArityMismatch {
+                operator: operator.to_string(),
+                expected: 2,
+                found: args.len(),
+            });
+        }
+        Ok((self.resolve(args[0])?, self.resolve(args[1])?))
+    }
+}
+
+/// The result of evaluating an [`OrbitMap::query`] call: either a set
+/// of objects (`orbits`, `path`, ...) or a single count
+/// (`count_orbits`).
+#[derive(Debug, PartialEq)]
+pub enum QueryResult {
+    Objects(Vec<OrbitalObject>),
+    Count(usize),
+}
+
+/// Why an [`OrbitMap::query`] call failed.
+#[derive(Debug, PartialEq)]
+pub enum QueryError {
+    UnknownOperator(String),
+    UnknownObject(String),
+    ArityMismatch {
+        operator: String,
+        expected: usize,
+        found: usize,
+    },
+    Malformed(String),
+    NoPath(String, String),
+    NoCommonAncestor(String, String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::UnknownOperator(op) => write!(f, "unknown operator {:?}", op),
+            QueryError::UnknownObject(name) => write!(f, "unknown object {:?}", name),
+            QueryError::ArityMismatch {
+                operator,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{:?} expects {} argument(s), found {}",
+                operator, expected, found
+            ),
+            QueryError::Malformed(reason) => write!(f, "malformed query: {}", reason),
+            QueryError::NoPath(a, b) => {
+                write!(f, "no transfer path between {:?} and {:?}", a, b)
+            }
+            QueryError::NoCommonAncestor(a, b) => {
+                write!(f, "{:?} and {:?} share no common ancestor", a, b)
+            }
         }
-        let path: Vec<&OrbitalObject> = you_orbits[..last_matching_you_index]
-            .into_iter()
-            .chain(santa_orbits[..=last_matching_santa_index].into_iter().rev())
-            .collect();
-        path.len() - 1
     }
 }
 
+impl std::error::Error for QueryError {}
+
+/// Splits a query string into identifier, `(`, `)`, and `,` tokens,
+/// skipping whitespace. Any other run of characters up to the next
+/// whitespace, parenthesis or comma becomes one identifier token.
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' || c == ',' {
+            tokens.push(c.to_string());
+            chars.next();
+        } else {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '(' || c == ')' || c == ',' {
+                    break;
+                }
+                ident.push(c);
+                chars.next();
+            }
+            tokens.push(ident);
+        }
+    }
+    tokens
+}
+
 /**
 OrbitMap {
     lookup: {
@@ -226,6 +707,316 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_transfers_between_generalizes_beyond_you_and_santa() {
+        let input = "COM)B
+        B)C
+        C)D
+        D)E
+        E)F
+        B)G
+        G)H
+        D)I
+        E)J
+        J)K
+        K)L
+        K)YOU
+        I)SAN";
+        let orbit_map = OrbitMap::parse(input);
+        assert_eq!(
+            orbit_map.transfers_between(&OrbitalObject::from("YOU"), &OrbitalObject::from("SAN")),
+            Some(4)
+        );
+        assert_eq!(
+            orbit_map.transfers_between(&OrbitalObject::from("L"), &OrbitalObject::from("H")),
+            Some(orbit_map.number_of_orbital_transfers_from_you_to_santa() + 2)
+        );
+    }
+
+    #[test]
+    fn test_orbital_transfers_between_resolves_names_and_matches_transfers_between() {
+        let input = "COM)B
+        B)C
+        C)D
+        D)E
+        E)F
+        B)G
+        G)H
+        D)I
+        E)J
+        J)K
+        K)L
+        K)YOU
+        I)SAN";
+        let orbit_map = OrbitMap::parse(input);
+        assert_eq!(orbit_map.orbital_transfers_between("YOU", "SAN"), Some(4));
+        assert_eq!(
+            orbit_map.orbital_transfers_between("L", "H"),
+            orbit_map.transfers_between(&OrbitalObject::from("L"), &OrbitalObject::from("H"))
+        );
+    }
+
+    #[test]
+    fn test_orbital_transfers_between_is_none_for_unknown_or_rootless_objects() {
+        let input = "COM)B
+        B)YOU
+        B)SAN";
+        let orbit_map = OrbitMap::parse(input);
+        assert_eq!(orbit_map.orbital_transfers_between("YOU", "NOPE"), None);
+        assert_eq!(orbit_map.orbital_transfers_between("COM", "SAN"), None);
+    }
+
+    #[test]
+    fn test_transfers_between_is_none_for_disjoint_trees() {
+        let input = "COM)A
+        B)C";
+        let orbit_map = OrbitMap::parse(input);
+        assert_eq!(orbit_map.orbital_transfers_between("A", "C"), None);
+        assert_eq!(
+            orbit_map.lowest_common_ancestor(&OrbitalObject::from("A"), &OrbitalObject::from("C")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_query_common_ancestor_is_descriptive_for_disjoint_trees() {
+        let orbit_map = OrbitMap::parse(
+            "COM)A
+            B)C",
+        );
+        let err = orbit_map.query("common_ancestor(A, C)").unwrap_err();
+        assert_eq!(
+            err,
+            QueryError::NoCommonAncestor("A".to_string(), "C".to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_mermaid_emits_one_edge_per_adjacency_and_styles_you_and_santa() {
+        let input = "COM)B
+        B)C
+        C)D
+        D)E
+        E)F
+        B)G
+        G)H
+        D)I
+        E)J
+        J)K
+        K)L
+        K)YOU
+        I)SAN";
+        let orbit_map = OrbitMap::parse(input);
+        let diagram = orbit_map.to_mermaid(false);
+        assert!(diagram.starts_with("graph TD"));
+        assert!(diagram.contains("COM[\"COM\"] --> B[\"B\"]"));
+        assert!(diagram.contains("style YOU fill:#f9f,stroke:#333,stroke-width:2px"));
+        assert!(diagram.contains("style SAN fill:#9f9,stroke:#333,stroke-width:2px"));
+    }
+
+    #[test]
+    fn test_to_mermaid_can_highlight_the_transfer_path() {
+        let input = "COM)B
+        B)C
+        C)D
+        D)E
+        E)F
+        B)G
+        G)H
+        D)I
+        E)J
+        J)K
+        K)L
+        K)YOU
+        I)SAN";
+        let orbit_map = OrbitMap::parse(input);
+        let diagram = orbit_map.to_mermaid(true);
+        assert!(diagram.contains("style K stroke:#f00,stroke-width:3px"));
+        assert!(diagram.contains("style D stroke:#f00,stroke-width:3px"));
+    }
+
+    #[test]
+    fn test_object_has_indirect_orbit_to_other_object() {
+        let input = "COM)B
+        B)C
+        C)D
+        D)E
+        E)F
+        B)G
+        G)H
+        D)I
+        E)J
+        J)K
+        K)L";
+        let orbit_map = OrbitMap::parse(input);
+        let d = OrbitalObject::from("D");
+        let e = OrbitalObject::from("E");
+        let com = OrbitalObject::from("COM");
+
+        assert!(orbit_map.object_has_direct_orbit_to_other_object(&e, &d));
+        assert!(!orbit_map.object_has_indirect_orbit_to_other_object(&e, &d));
+        assert!(orbit_map.object_has_indirect_orbit_to_other_object(&e, &com));
+        assert!(!orbit_map.object_has_indirect_orbit_to_other_object(&com, &e));
+    }
+
+    #[test]
+    fn test_ancestors_and_descendant_queries() {
+        let input = "COM)B
+        B)C
+        C)D
+        D)E
+        E)F
+        B)G
+        G)H
+        D)I
+        E)J
+        J)K
+        K)L";
+        let orbit_map = OrbitMap::parse(input);
+        let l = OrbitalObject::from("L");
+        let k = OrbitalObject::from("K");
+        let com = OrbitalObject::from("COM");
+
+        let ancestors: Vec<OrbitalObject> = orbit_map.ancestors(&l).collect();
+        assert_eq!(
+            ancestors,
+            vec![
+                OrbitalObject::from("K"),
+                OrbitalObject::from("J"),
+                OrbitalObject::from("E"),
+                OrbitalObject::from("D"),
+                OrbitalObject::from("C"),
+                OrbitalObject::from("B"),
+                OrbitalObject::from("COM"),
+            ]
+        );
+        assert!(orbit_map.is_ancestor_of(&com, &l));
+        assert!(orbit_map.is_descendant_of(&l, &com));
+        assert!(!orbit_map.is_ancestor_of(&l, &com));
+        assert!(orbit_map.is_ancestor_of(&k, &l));
+    }
+
+    fn sample_orbit_map() -> OrbitMap {
+        OrbitMap::parse(
+            "COM)B
+            B)C
+            C)D
+            D)E
+            E)F
+            B)G
+            G)H
+            D)I
+            E)J
+            J)K
+            K)L
+            K)YOU
+            I)SAN",
+        )
+    }
+
+    #[test]
+    fn test_query_orbits_returns_ancestor_chain() {
+        let orbit_map = sample_orbit_map();
+        let result = orbit_map.query("orbits(L)").unwrap();
+        assert_eq!(
+            result,
+            QueryResult::Objects(orbit_map.ancestors(&OrbitalObject::from("L")).collect())
+        );
+    }
+
+    #[test]
+    fn test_query_orbited_by_returns_descendants() {
+        let orbit_map = sample_orbit_map();
+        let result = orbit_map.query("orbited_by(D)").unwrap();
+        match result {
+            QueryResult::Objects(mut objects) => {
+                objects.sort_by_key(|obj| obj.mermaid_label().to_string());
+                let mut expected = vec![
+                    OrbitalObject::from("E"),
+                    OrbitalObject::from("F"),
+                    OrbitalObject::from("I"),
+                    OrbitalObject::from("J"),
+                    OrbitalObject::from("K"),
+                    OrbitalObject::from("L"),
+                    OrbitalObject::Santa,
+                    OrbitalObject::You,
+                ];
+                expected.sort_by_key(|obj| obj.mermaid_label().to_string());
+                assert_eq!(objects, expected);
+            }
+            other => panic!("expected Objects, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_query_common_ancestor() {
+        let orbit_map = sample_orbit_map();
+        let result = orbit_map.query("common_ancestor(YOU, SAN)").unwrap();
+        assert_eq!(
+            result,
+            QueryResult::Objects(vec![OrbitalObject::from("D")])
+        );
+    }
+
+    #[test]
+    fn test_query_path_matches_transfer_path() {
+        let orbit_map = sample_orbit_map();
+        let result = orbit_map.query("path(YOU, SAN)").unwrap();
+        assert_eq!(
+            result,
+            QueryResult::Objects(
+                orbit_map
+                    .transfer_path_between(&OrbitalObject::You, &OrbitalObject::Santa)
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_query_path_from_com_is_descriptive_not_a_panic() {
+        let orbit_map = sample_orbit_map();
+        let result = orbit_map.query("path(COM, SAN)");
+        assert_eq!(
+            result,
+            Err(QueryError::NoPath("COM".to_string(), "SAN".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_query_count_orbits() {
+        let orbit_map = sample_orbit_map();
+        let result = orbit_map.query("count_orbits").unwrap();
+        assert_eq!(result, QueryResult::Count(orbit_map.number_of_orbits()));
+    }
+
+    #[test]
+    fn test_query_unknown_operator_is_descriptive() {
+        let orbit_map = sample_orbit_map();
+        let err = orbit_map.query("frobnicate(YOU)").unwrap_err();
+        assert_eq!(err, QueryError::UnknownOperator("frobnicate".to_string()));
+    }
+
+    #[test]
+    fn test_query_unknown_object_is_descriptive() {
+        let orbit_map = sample_orbit_map();
+        let err = orbit_map.query("orbits(NOPE)").unwrap_err();
+        assert_eq!(err, QueryError::UnknownObject("NOPE".to_string()));
+    }
+
+    #[test]
+    fn test_query_arity_mismatch_is_descriptive() {
+        let orbit_map = sample_orbit_map();
+        let err = orbit_map.query("orbits(YOU, SAN)").unwrap_err();
+        assert_eq!(
+            err,
+            QueryError::ArityMismatch {
+                operator: "orbits".to_string(),
+                expected: 1,
+                found: 2,
+            }
+        );
+    }
+
     #[test]
     fn test_advent_puzzle() {
         let input = "XV5)LZ5
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+pub type AdjacencyList<S> = HashMap<S, Vec<S>>;
+
+/// A directed graph over nodes of type `S`, tracking both forward and
+/// reverse adjacency so traversals and "what points here" queries are
+/// both O(1) to look up. Generic so any day's graph puzzle (not just
+/// the orbit map) can reuse the traversal combinators below instead of
+/// hand-rolling BFS/DFS again.
+#[derive(Debug, Clone)]
+pub struct Graph<S: Hash + Eq + Clone> {
+    nodes: HashSet<S>,
+    forward: AdjacencyList<S>,
+    reverse: AdjacencyList<S>,
+}
+
+impl<S: Hash + Eq + Clone> Default for Graph<S> {
+    fn default() -> Self {
+        Graph {
+            nodes: HashSet::new(),
+            forward: HashMap::new(),
+            reverse: HashMap::new(),
+        }
+    }
+}
+
+impl<S: Hash + Eq + Clone> Graph<S> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn add_edge(&mut self, from: S, to: S) {
+        self.nodes.insert(from.clone());
+        self.nodes.insert(to.clone());
+        self.forward
+            .entry(from.clone())
+            .or_insert_with(Vec::new)
+            .push(to.clone());
+        self.reverse.entry(to).or_insert_with(Vec::new).push(from);
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &S> {
+        self.nodes.iter()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&S, &S)> {
+        self.forward
+            .iter()
+            .flat_map(|(from, tos)| tos.iter().map(move |to| (from, to)))
+    }
+
+    pub fn neighbors(&self, node: &S) -> &[S] {
+        self.forward.get(node).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn predecessors(&self, node: &S) -> &[S] {
+        self.reverse.get(node).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Breadth-first traversal order starting from `start`, `start`
+    /// included.
+    pub fn bfs(&self, start: &S) -> Vec<S> {
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(start.clone());
+        visited.insert(start.clone());
+        while let Some(node) = queue.pop_front() {
+            for neighbor in self.neighbors(&node) {
+                if visited.insert(neighbor.clone()) {
+                    queue.push_back(neighbor.clone());
+                }
+            }
+            order.push(node);
+        }
+        order
+    }
+
+    /// Depth-first traversal order starting from `start`, `start`
+    /// included.
+    pub fn dfs(&self, start: &S) -> Vec<S> {
+        let mut visited = HashSet::new();
+        let mut order = Vec::new();
+        let mut stack = vec![start.clone()];
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node.clone()) {
+                continue;
+            }
+            for neighbor in self.neighbors(&node).iter().rev() {
+                if !visited.contains(neighbor) {
+                    stack.push(neighbor.clone());
+                }
+            }
+            order.push(node);
+        }
+        order
+    }
+
+    /// The shortest path from `start` to `end` (inclusive of both
+    /// endpoints), or `None` if `end` isn't reachable.
+    pub fn shortest_path(&self, start: &S, end: &S) -> Option<Vec<S>> {
+        let mut visited = HashSet::new();
+        let mut came_from: HashMap<S, S> = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(start.clone());
+        visited.insert(start.clone());
+
+        while let Some(node) = queue.pop_front() {
+            if node == *end {
+                let mut path = vec![node.clone()];
+                let mut current = node;
+                while let Some(prev) = came_from.get(&current) {
+                    path.push(prev.clone());
+                    current = prev.clone();
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for neighbor in self.neighbors(&node) {
+                if visited.insert(neighbor.clone()) {
+                    came_from.insert(neighbor.clone(), node.clone());
+                    queue.push_back(neighbor.clone());
+                }
+            }
+        }
+        None
+    }
+
+    /// Builds a new graph by applying `f` to every node, preserving
+    /// edges between their mapped counterparts.
+    pub fn map_nodes<T, F>(&self, f: F) -> Graph<T>
+    where
+        T: Hash + Eq + Clone,
+        F: Fn(&S) -> T,
+    {
+        let mut mapped = Graph::new();
+        for (from, to) in self.edges() {
+            mapped.add_edge(f(from), f(to));
+        }
+        mapped
+    }
+
+    /// Builds a new graph keeping only the edges `predicate` accepts.
+    pub fn filter_edges<F>(&self, predicate: F) -> Graph<S>
+    where
+        F: Fn(&S, &S) -> bool,
+    {
+        let mut filtered = Graph::new();
+        for (from, to) in self.edges() {
+            if predicate(from, to) {
+                filtered.add_edge(from.clone(), to.clone());
+            }
+        }
+        filtered
+    }
+
+    /// Folds over every node reachable (via BFS) from `start`.
+    pub fn fold<B, F>(&self, start: &S, init: B, f: F) -> B
+    where
+        F: FnMut(B, &S) -> B,
+    {
+        let mut f = f;
+        self.bfs(start).iter().fold(init, &mut f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b");
+        graph.add_edge("b", "c");
+        graph.add_edge("a", "c");
+        graph
+    }
+
+    #[test]
+    fn test_bfs_visits_every_reachable_node_once() {
+        let graph = sample();
+        let mut order = graph.bfs(&"a");
+        order.sort();
+        assert_eq!(order, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_shortest_path_prefers_fewer_hops() {
+        let graph = sample();
+        assert_eq!(graph.shortest_path(&"a", &"c"), Some(vec!["a", "c"]));
+    }
+
+    #[test]
+    fn test_shortest_path_is_none_when_unreachable() {
+        let graph = sample();
+        assert_eq!(graph.shortest_path(&"c", &"a"), None);
+    }
+
+    #[test]
+    fn test_predecessors_and_neighbors_agree_with_added_edges() {
+        let graph = sample();
+        assert_eq!(graph.neighbors(&"a"), &["b", "c"]);
+        assert_eq!(graph.predecessors(&"c"), &["b", "a"]);
+    }
+
+    #[test]
+    fn test_map_nodes_preserves_edge_structure() {
+        let graph = sample();
+        let mapped = graph.map_nodes(|s| s.to_uppercase());
+        assert_eq!(mapped.neighbors(&"A".to_string()), &["B", "C"]);
+    }
+
+    #[test]
+    fn test_filter_edges_drops_rejected_edges() {
+        let graph = sample();
+        let filtered = graph.filter_edges(|from, _to| *from != "a");
+        assert_eq!(filtered.neighbors(&"a"), &[] as &[&str]);
+        assert_eq!(filtered.neighbors(&"b"), &["c"]);
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+
+/// Where a [`Intcode::run_until_blocked`] call left off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunState {
+    Halted,
+    NeedsInput,
+}
+
+/// An Intcode VM that suspends instead of blocking when it hits an
+/// input opcode with nothing queued, so several instances can be
+/// wired together (amplifier chains, the day-23 NIC network) and
+/// pumped in lockstep rather than each owning a blocking I/O closure.
+#[derive(Debug)]
+pub struct Intcode {
+    memory: Vec<i64>,
+    pointer: usize,
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
+    halted: bool,
+}
+
+impl Intcode {
+    pub fn new(memory: Vec<i64>) -> Self {
+        Intcode {
+            memory,
+            pointer: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            halted: false,
+        }
+    }
+
+    /// Queues a value for the next input opcode the VM executes.
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    /// Takes every value the VM has output since the last drain.
+    pub fn drain_output(&mut self) -> Vec<i64> {
+        self.output.drain(..).collect()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Runs to completion, stopping early at an input opcode if no input
+    /// has been queued for it (the instruction pointer stays on that
+    /// opcode, so a later run resumes there). Callers that want to
+    /// supply input on demand should drive [`Intcode::run_until_blocked`]
+    /// directly instead.
+    pub fn run(&mut self) {
+        self.run_until_blocked();
+    }
+
+    /// Executes until the VM halts or hits an input opcode with an
+    /// empty input queue, preserving the instruction pointer so the
+    /// same instance can be resumed by calling this again later.
+    pub fn run_until_blocked(&mut self) -> RunState {
+        loop {
+            let instruction = self.memory[self.pointer] as usize;
+            let opcode = instruction % 100;
+            let modes = instruction / 100;
+
+            match opcode {
+                1 | 2 | 7 | 8 => {
+                    let a = self.read_param(1, modes);
+                    let b = self.read_param(2, modes);
+                    let dest = self.memory[self.pointer + 3] as usize;
+                    self.memory[dest] = match opcode {
+                        1 => a + b,
+                        2 => a * b,
+                        7 => (a < b) as i64,
+                        8 => (a == b) as i64,
+                        _ => unreachable!(),
+                    };
+                    self.pointer += 4;
+                }
+                3 => match self.input.pop_front() {
+                    Some(value) => {
+                        let dest = self.memory[self.pointer + 1] as usize;
+                        self.memory[dest] = value;
+                        self.pointer += 2;
+                    }
+                    None => return RunState::NeedsInput,
+                },
+                4 => {
+                    let value = self.read_param(1, modes);
+                    self.output.push_back(value);
+                    self.pointer += 2;
+                }
+                5 | 6 => {
+                    let a = self.read_param(1, modes);
+                    let b = self.read_param(2, modes);
+                    let should_jump = if opcode == 5 { a != 0 } else { a == 0 };
+                    self.pointer = if should_jump {
+                        b as usize
+                    } else {
+                        self.pointer + 3
+                    };
+                }
+                99 => {
+                    self.halted = true;
+                    return RunState::Halted;
+                }
+                other => panic!("unknown opcode {}", other),
+            }
+        }
+    }
+
+    fn read_param(&self, offset: usize, modes: usize) -> i64 {
+        let mode = (modes / 10usize.pow(offset as u32 - 1)) % 10;
+        let raw = self.memory[self.pointer + offset];
+        match mode {
+            0 => self.memory[raw as usize],
+            1 => raw,
+            other => panic!("unknown parameter mode {}", other),
+        }
+    }
+
+    pub fn dump_memory(&self) -> String {
+        format!("{:?}", self.memory)
+    }
+}
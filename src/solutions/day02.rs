@@ -1,25 +1,25 @@
 #[cfg(test)]
 mod tests {
-    use crate::intcode::Intcode;
+    use crate::intcode::{Intcode, RunState};
     use crate::utils;
 
     #[test]
     fn smoke_simple_program_1() {
-        let mut program = Intcode::new(vec![1, 0, 0, 0, 99], || 0, |_| {});
+        let mut program = Intcode::new(vec![1, 0, 0, 0, 99]);
         program.run();
         assert_eq!(program.dump_memory(), String::from("[2, 0, 0, 0, 99]"));
     }
 
     #[test]
     fn smoke_simple_program_2() {
-        let mut program = Intcode::new(vec![2, 3, 0, 3, 99], || 0, |_| {});
+        let mut program = Intcode::new(vec![2, 3, 0, 3, 99]);
         program.run();
         assert_eq!(program.dump_memory(), String::from("[2, 3, 0, 6, 99]"));
     }
 
     #[test]
     fn smoke_simple_program_3() {
-        let mut program = Intcode::new(vec![2, 4, 4, 5, 99, 0], || 0, |_| {});
+        let mut program = Intcode::new(vec![2, 4, 4, 5, 99, 0]);
         program.run();
         assert_eq!(
             program.dump_memory(),
@@ -29,7 +29,7 @@ mod tests {
 
     #[test]
     fn smoke_simple_program_4() {
-        let mut program = Intcode::new(vec![1, 1, 1, 4, 99, 5, 6, 0, 99], || 0, |_| {});
+        let mut program = Intcode::new(vec![1, 1, 1, 4, 99, 5, 6, 0, 99]);
         program.run();
         assert_eq!(
             program.dump_memory(),
@@ -37,6 +37,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_run_until_blocked_suspends_on_missing_input() {
+        // 3,0,4,0,99: read input into address 0, echo it back out, halt.
+        let mut program = Intcode::new(vec![3, 0, 4, 0, 99]);
+        assert_eq!(program.run_until_blocked(), RunState::NeedsInput);
+        assert!(!program.is_halted());
+
+        program.push_input(42);
+        assert_eq!(program.run_until_blocked(), RunState::Halted);
+        assert_eq!(program.drain_output(), vec![42]);
+    }
+
     #[test]
     fn test_advent_puzzle() {
         let rom = utils::load_input_file(
@@ -44,7 +56,7 @@ mod tests {
             utils::parse_comma_separated_content_into_vec_of_fromstr_data,
         )
         .unwrap();
-        let mut program = Intcode::new(rom, || 0, |_| {});
+        let mut program = Intcode::new(rom);
         program.run();
         assert!(program.dump_memory().starts_with("[12490719,"));
     }
@@ -0,0 +1,34 @@
+use std::fmt;
+use std::io;
+
+/// The error type for loading input (`Input::new`) and for parsing it
+/// with `Input::try_into_vec`, so those callers don't have to juggle
+/// `io::Error` in one place and a parser's own `Err` type in another.
+/// `Input::try_into` and `Input::parse_lines` return the parser's own
+/// `Err` type instead.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse { line: usize, content: String },
+    InputNotFound(String),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "io error: {}", err),
+            Error::Parse { line, content } => {
+                write!(f, "failed to parse line {}: {:?}", line, content)
+            }
+            Error::InputNotFound(name) => write!(f, "input not found: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
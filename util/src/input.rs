@@ -5,15 +5,22 @@ use std::io::Read;
 use std::path::Path;
 use std::str::FromStr;
 
+use crate::error::Error;
+
 #[derive(Debug, Default)]
 pub struct Input(String);
 
 impl Input {
-    pub fn new(name: &str, _year: &'static str) -> Result<Self, io::Error> {
+    pub fn new(name: &str, _year: &'static str) -> Result<Self, Error> {
         let input_file = Path::new("src/inputs/").join(name);
         let mut contents = String::new();
 
-        File::open(input_file).and_then(|mut file| file.read_to_string(&mut contents))?;
+        File::open(&input_file)
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::NotFound => Error::InputNotFound(name.to_string()),
+                _ => Error::Io(err),
+            })?;
 
         Ok(Input(contents))
     }
@@ -25,15 +32,44 @@ impl Input {
         T::from_str(&self.0)
     }
 
-    pub fn into_vec<T>(self, sep: &str) -> Vec<T>
+    /// Parses each non-empty line into a `T` via its `FromStr` impl,
+    /// returning the zero-based line index and parse error on the
+    /// first failure. Suited to days whose records are one `FromStr`
+    /// type per line (e.g. day 2's `Password`).
+    pub fn parse_lines<T>(&self) -> Result<Vec<T>, (usize, T::Err)>
     where
         T: FromStr,
     {
         self.0
-            .split(sep)
-            .filter(|s| !s.is_empty()) // skip empty lines
-            .map(T::from_str)
-            .filter_map(Result::ok)
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| T::from_str(line).map_err(|err| (i, err)))
+            .collect()
+    }
+
+    /// Parses each non-empty `sep`-delimited segment into a `T`,
+    /// returning the offending zero-based line number and raw text on
+    /// the first failure instead of silently dropping it.
+    pub fn try_into_vec<T>(self, sep: &str) -> Result<Vec<T>, Error>
+    where
+        T: FromStr,
+    {
+        let text = &self.0;
+        let mut offset = 0;
+        text.split(sep)
+            .map(|segment| {
+                let start = offset;
+                offset += segment.len() + sep.len();
+                (start, segment)
+            })
+            .filter(|(_, segment)| !segment.is_empty())
+            .map(|(start, segment)| {
+                T::from_str(segment).map_err(|_| Error::Parse {
+                    line: text[..start].matches('\n').count(),
+                    content: segment.to_string(),
+                })
+            })
             .collect()
     }
 }
@@ -0,0 +1,8 @@
+//! Shared helpers for reading and parsing puzzle input, used across the
+//! day-by-day solutions.
+
+pub mod error;
+pub mod input;
+
+pub use error::Error;
+pub use input::Input;